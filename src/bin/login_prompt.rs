@@ -0,0 +1,13 @@
+//! Minimal prompt/response program for exercising `script:`.
+
+use std::io::{stdin, stdout, BufRead, Write};
+
+fn main() {
+    print!("Name: ");
+    stdout().flush().unwrap();
+
+    let mut name = String::new();
+    stdin().lock().read_line(&mut name).unwrap();
+
+    println!("Welcome, {}!", name.trim());
+}
@@ -76,6 +76,10 @@ If a custom PATH path is provided via the `env` key, it is modified as well.
 #![allow(unused)]
 
 extern crate wait_timeout;
+extern crate regex;
+extern crate tempfile;
+#[cfg(unix)]
+extern crate nix;
 
 use std::ffi::{OsString, OsStr};
 use std::collections::VecDeque;
@@ -87,6 +91,18 @@ use std::time::Duration;
 #[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 use std::fmt::Debug;
+use regex::Regex;
+#[cfg(unix)]
+use std::process::{Command, Stdio};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(unix)]
+use std::io;
+use std::io::{Read, Write};
+use std::mem;
+use std::thread;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 /// Comfortably execute a command and its output.
 ///
@@ -176,6 +192,38 @@ use std::fmt::Debug;
 /// };
 /// ```
 ///
+/// ## `script` (`impl IntoIter<Item = ScriptStep>`)
+///
+/// A single static `stdin` can't drive REPL-style or prompt-driven programs
+/// that must read a prompt before the next input is valid. `script` takes an
+/// ordered list of [`expect()`] and [`send()`] steps instead: a background
+/// thread reads the child's stdout incrementally while the main thread waits
+/// (within the `timeout` budget, if any) for each `expect()` to appear in the
+/// accumulated output before writing the next `send()` to stdin.
+///
+/// If an expectation never appears in time, the child is killed and the
+/// macro panics showing what was expected and what was actually seen so
+/// far. On success, the fully captured stdout (as read by the script) is
+/// still returned through the normal [`Output`].
+///
+/// Unlike `args`, this key needs `vec![]` rather than an array literal: an
+/// owned array iterates by reference, which `args` accepts but `script`,
+/// taking its `ScriptStep`s by value, does not.
+///
+/// ```
+/// exec!{
+///     "login_prompt",
+///     script: vec![
+///         expect(b"Name: "),
+///         send(b"root\n"),
+///         expect(b"Welcome, root!\n")
+///     ]
+/// };
+/// ```
+///
+/// [`expect()`]: fn.expect.html
+/// [`send()`]: fn.send.html
+///
 /// ## `timeout` (`u64`)
 ///
 /// Set the maximum running time for the program in *milliseconds*.
@@ -198,6 +246,29 @@ use std::fmt::Debug;
 /// };
 /// ```
 ///
+/// ## `term_signal` and `kill_grace` (`i32` and `u64`)
+///
+/// > ! Supported Unix only, ignored on Windows
+///
+/// By default a timeout kills the child immediately with `SIGKILL`, which
+/// never gives it a chance to react. Setting either key switches to a
+/// graceful shutdown: `term_signal` is sent first (`SIGTERM`, `15`, if
+/// omitted), and only if the child is still alive after `kill_grace`
+/// milliseconds (`1000` if omitted) is it finally killed with `SIGKILL`.
+/// The returned status reflects whichever signal actually reaped the child.
+///
+/// ```
+/// exec!{
+///     "sleep",
+///     args: ["60"],
+///     timeout: 3000,
+///     kill_grace: 500,
+///
+///     code: 15, // SIGTERM
+///     signal: 15
+/// };
+/// ```
+///
 /// ## `log` (`bool`)
 ///
 /// By setting this to `true`, the output of the program is logged after
@@ -205,6 +276,80 @@ use std::fmt::Debug;
 ///
 /// Remember to pass `--nocapture` to tests using this option.
 ///
+/// ## `pty` (`bool`)
+///
+/// > ! Supported Unix only, compile error on Windows
+///
+/// Run the program attached to a pseudo-terminal instead of plain pipes,
+/// so it behaves as if run interactively (colorized output, progress bars,
+/// line-buffering, ...). `stdin`, `stdout` and `stderr` all share the pty,
+/// so captured output of both streams ends up combined in `stdout`.
+///
+/// ```
+/// #[cfg(unix)]
+/// exec!{
+///     "tput",
+///     args: ["colors"],
+///     pty: true,
+///
+///     stdout: b"256\r\n" // the pty maps `\n` to `\r\n` on output
+/// };
+/// ```
+///
+/// ## `rlimit` (pseudo-object)
+///
+/// > ! Supported Unix only, compile error on Windows
+///
+/// Apply resource limits (`setrlimit(2)`) to the child before it execs, as a
+/// pseudo-object mapping `RLIMIT_*` names (without the `RLIMIT_` prefix) to
+/// either a single soft-and-hard value or a `(soft, hard)` tuple. `-1` means
+/// `RLIM_INFINITY`. Supported names are `CPU`, `FSIZE`, `DATA`, `STACK`,
+/// `CORE`, `RSS`, `NOFILE`, `AS`, `NPROC` and `MEMLOCK`.
+///
+/// ```
+/// #[cfg(unix)]
+/// exec!{
+///     "dd",
+///     args: ["if=/dev/zero", "of=/tmp/out", "bs=1024", "count=2"],
+///     rlimit: {
+///         FSIZE: 1024,
+///         NOFILE: (16, 32)
+///     },
+///
+///     code: 25, // SIGXFSZ, the default uncaught disposition for exceeding RLIMIT_FSIZE
+///     signal: 25
+/// };
+/// ```
+///
+/// ## `fixtures` (pseudo-object)
+///
+/// Seed a fresh temporary directory with files before the program runs, and
+/// use it as the child's working directory. Keys are the file paths
+/// (relative to the temp dir, parent directories are created as needed) and
+/// values `impl AsRef<[u8]>` content. Using `fixtures`, `expect_file` or
+/// `expect_absent` together with an explicit `cwd` key is not supported,
+/// since the temp dir *is* the working directory; combining them panics.
+///
+/// The temp dir is removed when the program's output matches all configured
+/// assertions, and preserved for debugging when any assertion fails, whether
+/// that's `expect_file`/`expect_absent` (whose path is printed in the panic
+/// message itself) or an earlier one like `code`/`stdout` (whose path is
+/// printed to stderr once the panic unwinds past `exec!`).
+///
+/// ```
+/// exec!{
+///     "cp",
+///     args: ["in.txt", "out.txt"],
+///     fixtures: {
+///         "in.txt" => b"data"
+///     },
+///
+///     expect_file: {
+///         "out.txt" => b"data"
+///     }
+/// };
+/// ```
+///
 /// # Output comparison
 ///
 /// `exec` offers various ways to compare the output of the program directly through the macro.
@@ -249,6 +394,46 @@ use std::fmt::Debug;
 /// };
 /// ```
 ///
+/// ## `stdout_contains`, `stdout_matches` and `stdout_fn` (and their `stderr_` equivalents)
+///
+/// Exact comparison is brittle for programs that emit timestamps, paths or version banners.
+/// These keys coexist with `stdout`/`stderr` and offer partial matching instead:
+///
+/// - `stdout_contains` (`impl AsRef<[u8]>`): make sure the byte sequence occurs somewhere in the output.
+/// - `stdout_matches` (`impl AsRef<str>`): make sure a regular expression matches the UTF-8 lossy output.
+/// - `stdout_fn` (`Fn(&[u8]) -> bool`): make sure a custom predicate returns `true` for the raw bytes.
+///
+/// ```
+/// exec!{
+///     "echo",
+///     args: ["Hello world!"],
+///
+///     stdout_contains: b"world",
+///     stdout_matches: r"^Hello \w+!\n$",
+///     stdout_fn: |bytes: &[u8]| !bytes.is_empty(),
+///     stderr_contains: []
+/// };
+/// ```
+///
+/// ## `expect_file` and `expect_absent` (pseudo-object and `impl IntoIter<Item = impl AsRef<str>>`)
+///
+/// Assert on the filesystem state of the `fixtures` temp dir after the
+/// program ran. `expect_file` maps paths to the expected `impl AsRef<[u8]>`
+/// content, `expect_absent` lists paths that must not exist. Both require
+/// `fixtures` (or at least one of the two) to have set up the temp dir.
+///
+/// ```
+/// exec!{
+///     "rm",
+///     args: ["lock"],
+///     fixtures: {
+///         "lock" => b""
+///     },
+///
+///     expect_absent: ["lock"]
+/// };
+/// ```
+///
 /// ## `signal` (`i32`)
 ///
 /// > ! Supported Unix only, ignored on Windows
@@ -278,13 +463,27 @@ macro_rules! exec {
         $(, env: { $( $key:ident : $value:expr ),* } )? // the environment variables as pseudo-object
         $(, modify_path: $modify_path:expr)? // enable the auto-modification of the PATH to include bin targets
         $(, stdin: $stdin:expr)? // what to write to the program's stdin
+        $(, script: $script:expr)? // ordered send/expect steps for interactive, stdin-driven programs
         $(, timeout: $timeout:expr)? // maximum allowed running time of the program
+        $(, term_signal: $term_signal:expr)? // signal sent first on timeout, before escalating to SIGKILL
+        $(, kill_grace: $kill_grace:expr)? // milliseconds to wait after term_signal before sending SIGKILL
         $(, log: $log:expr)? // log the output
+        $(, pty: $pty:expr)? // run the program attached to a pseudo-terminal
+        $(, rlimit: { $( $rkey:ident : $rvalue:expr ),* } )? // resource limits applied to the child
+        $(, fixtures: { $( $fixture_path:expr => $fixture_content:expr ),* } )? // files to seed the temp working directory with
 
         // Assertions
         $(, code: $code:expr)? // the expected exit code
         $(, stdout: $stdout:expr)? // the expected stdout
         $(, stderr: $stderr:expr)? // the expected stderr
+        $(, stdout_contains: $stdout_contains:expr)? // bytes that must occur somewhere in stdout
+        $(, stdout_matches: $stdout_matches:expr)? // regex that must match the UTF-8 lossy stdout
+        $(, stdout_fn: $stdout_fn:expr)? // predicate that must return true for the raw stdout bytes
+        $(, stderr_contains: $stderr_contains:expr)? // bytes that must occur somewhere in stderr
+        $(, stderr_matches: $stderr_matches:expr)? // regex that must match the UTF-8 lossy stderr
+        $(, stderr_fn: $stderr_fn:expr)? // predicate that must return true for the raw stderr bytes
+        $(, expect_file: { $( $expect_file_path:expr => $expect_file_content:expr ),* } )? // expected file contents in the temp working directory
+        $(, expect_absent: $expect_absent:expr)? // paths that must not exist in the temp working directory
         $(, signal: $signal:expr)? // expected signal ID of the signal that terminated the program
     ) => {{
         use $crate::*;
@@ -309,8 +508,47 @@ macro_rules! exec {
             command.args($args.into_iter());
         )?
 
+        let mut has_cwd = false;
         $( // $cwd
             command.current_dir($cwd);
+            has_cwd = true;
+        )?
+
+        let mut temp_dir = TempDirGuard(None);
+        $( // $fixtures
+            {
+                if has_cwd {
+                    panic!("`fixtures` cannot be combined with an explicit `cwd`");
+                };
+                let dir = tempfile::tempdir().expect("Failed to create a temporary directory");
+                command.current_dir(dir.path());
+                $(
+                    write_fixture(dir.path(), AsRef::<str>::as_ref(&$fixture_path), AsRef::<[u8]>::as_ref(&$fixture_content));
+                )*
+                temp_dir.0 = Some(dir);
+            }
+        )?
+        $( // $expect_file, ensure a temp dir exists even without explicit fixtures
+            if temp_dir.0.is_none() {
+                if has_cwd {
+                    panic!("`expect_file` cannot be combined with an explicit `cwd`");
+                };
+                let dir = tempfile::tempdir().expect("Failed to create a temporary directory");
+                command.current_dir(dir.path());
+                temp_dir.0 = Some(dir);
+            };
+            $( let _ = (&$expect_file_path, &$expect_file_content); )* // trigger only when $expect_file is present
+        )?
+        $( // $expect_absent, ensure a temp dir exists even without explicit fixtures
+            if temp_dir.0.is_none() {
+                if has_cwd {
+                    panic!("`expect_absent` cannot be combined with an explicit `cwd`");
+                };
+                let dir = tempfile::tempdir().expect("Failed to create a temporary directory");
+                command.current_dir(dir.path());
+                temp_dir.0 = Some(dir);
+            };
+            let _ = &$expect_absent; // trigger only when $expect_absent is present
         )?
 
         $( // $clear_env
@@ -342,24 +580,96 @@ macro_rules! exec {
             command.env("PATH", path);
         };
 
+        $( // $rkey, $rvalue
+            #[cfg(unix)]
+            {
+                $(
+                    let (soft, hard) = IntoRlimit::into_rlimit($rvalue);
+                    set_rlimit(&mut command, stringify!($rkey), soft, hard);
+                )*
+            }
+            #[cfg(not(unix))]
+            compile_error!("the `rlimit` key is only supported on Unix");
+        )?
+
         $( // $stdin
             let _ = $stdin; // trigger this only when $stdin is present
             command.stdin(Stdio::piped());
         )?
+        $( // $script
+            let _ = &$script; // trigger this only when $script is present
+            command.stdin(Stdio::piped());
+        )?
+
+        #[cfg(unix)]
+        let mut pty_master: Option<std::fs::File> = None;
+        $( // $pty
+            #[cfg(unix)]
+            {
+                if $pty {
+                    pty_master = Some(attach_pty(&mut command));
+                }
+            }
+            #[cfg(not(unix))]
+            compile_error!("the `pty` key is only supported on Unix");
+        )?
 
         let mut child = command.spawn().expect("Failed to spawn child process");
+        // `command` owns the parent's copies of the pty slave fd (and its dups) via its
+        // stdin/stdout/stderr Stdio; reading the master (`read_pty`) only hits EIO once every
+        // fd of the slave is closed, so drop it now rather than keeping it open for the rest of `exec!`.
+        drop(command);
         $( // $stdin
             let stdin = &$stdin;
             let a = AsRef::<[u8]>::as_ref(&stdin);  // this syntax gives pretty, unambiguous type errors
-            child.stdin.as_mut()
-                .map(|buf|  buf.write_all(a).expect("Failed to write to stdin"));
+            #[cfg(unix)]
+            {
+                if let Some(ref mut master) = pty_master {
+                    master.write_all(a).expect("Failed to write to the pty");
+                    // the pty line discipline is canonical by default, so a line with no
+                    // trailing newline sits buffered until flushed; two EOF characters (^D)
+                    // flush it and then signal end-of-input, same as typing ^D^D interactively
+                    master.write_all(&[4, 4]).expect("Failed to write EOF to the pty");
+                } else {
+                    child.stdin.as_mut()
+                        .map(|buf|  buf.write_all(a).expect("Failed to write to stdin"));
+                }
+            }
+            #[cfg(not(unix))]
+            {
+                child.stdin.as_mut()
+                    .map(|buf|  buf.write_all(a).expect("Failed to write to stdin"));
+            }
         )?
 
         let mut duration = None;
         $( // $timeout
             duration = Some(Duration::from_millis($timeout as u64));
         )?
-        let status = wait(&mut child, duration);
+
+        let mut script_stdout: Option<Vec<u8>> = None;
+        $( // $script
+            script_stdout = Some(run_script(&mut child, $script.into_iter().collect::<Vec<_>>(), duration));
+        )?
+
+        let mut term_signal: Option<i32> = None;
+        $( // $term_signal
+            term_signal = Some($term_signal);
+        )?
+        let mut kill_grace: Option<Duration> = None;
+        $( // $kill_grace
+            kill_grace = Some(Duration::from_millis($kill_grace as u64));
+        )?
+        if term_signal.is_none() && kill_grace.is_some() {
+            term_signal = Some(15); // SIGTERM
+        };
+
+        let mut log = false;
+        $( // $log
+            log = $log;
+        )?
+
+        let status = wait(&mut child, duration, term_signal, kill_grace, log);
         let mut code = 0;
         $( // $code
             code = $code;
@@ -373,12 +683,30 @@ macro_rules! exec {
         )?
 
         let mut stdout = Vec::with_capacity(0xff);
-        let mut child_stdout = mem::replace(&mut child.stdout, None).unwrap();
-        io::copy(&mut child_stdout, &mut stdout).unwrap();
-
         let mut stderr = Vec::with_capacity(0xf);
-        let mut child_stderr = mem::replace(&mut child.stderr, None).unwrap();
-        io::copy(&mut child_stderr, &mut stderr).unwrap();
+
+        #[cfg(unix)]
+        let used_pty = pty_master.is_some();
+        #[cfg(not(unix))]
+        let used_pty = false;
+
+        if let Some(captured) = script_stdout {
+            stdout = captured;
+
+            let mut child_stderr = mem::replace(&mut child.stderr, None).unwrap();
+            io::copy(&mut child_stderr, &mut stderr).unwrap();
+        } else if used_pty {
+            #[cfg(unix)]
+            {
+                stdout = read_pty(pty_master.as_mut().unwrap());
+            }
+        } else {
+            let mut child_stdout = mem::replace(&mut child.stdout, None).unwrap();
+            io::copy(&mut child_stdout, &mut stdout).unwrap();
+
+            let mut child_stderr = mem::replace(&mut child.stderr, None).unwrap();
+            io::copy(&mut child_stderr, &mut stderr).unwrap();
+        }
 
         $( // $log
             if $log {
@@ -408,6 +736,58 @@ macro_rules! exec {
             assert(&a, &stderr.as_slice(), "Unexpected value of stderr");
         )?
 
+        $( // $stdout_contains
+            let expected_stdout_contains = &$stdout_contains;
+            let a = AsRef::<[u8]>::as_ref(expected_stdout_contains);
+            assert_contains(stdout.as_slice(), a, "stdout did not contain the expected bytes");
+        )?
+        $( // $stdout_matches
+            let pattern: &str = AsRef::<str>::as_ref(&$stdout_matches);
+            assert_matches(stdout.as_slice(), pattern, "stdout did not match the expected pattern");
+        )?
+        $( // $stdout_fn
+            assert_fn(stdout.as_slice(), $stdout_fn, "stdout predicate returned false");
+        )?
+        $( // $stderr_contains
+            let expected_stderr_contains = &$stderr_contains;
+            let a = AsRef::<[u8]>::as_ref(expected_stderr_contains);
+            assert_contains(stderr.as_slice(), a, "stderr did not contain the expected bytes");
+        )?
+        $( // $stderr_matches
+            let pattern: &str = AsRef::<str>::as_ref(&$stderr_matches);
+            assert_matches(stderr.as_slice(), pattern, "stderr did not match the expected pattern");
+        )?
+        $( // $stderr_fn
+            assert_fn(stderr.as_slice(), $stderr_fn, "stderr predicate returned false");
+        )?
+
+        let mut fs_failures: Vec<String> = Vec::new();
+        $( // $expect_file
+            {
+                let dir = temp_dir.0.as_ref().expect("`expect_file` requires `fixtures` to set up a temp directory").path();
+                $(
+                    let content = AsRef::<[u8]>::as_ref(&$expect_file_content);
+                    if let Some(message) = check_expect_file(dir, AsRef::<str>::as_ref(&$expect_file_path), content) {
+                        fs_failures.push(message);
+                    };
+                )*
+            }
+        )?
+        $( // $expect_absent
+            {
+                let dir = temp_dir.0.as_ref().expect("`expect_absent` requires `fixtures` to set up a temp directory").path();
+                for name in $expect_absent.into_iter() {
+                    if let Some(message) = check_expect_absent(dir, AsRef::<str>::as_ref(&name)) {
+                        fs_failures.push(message);
+                    };
+                };
+            }
+        )?
+        if !fs_failures.is_empty() {
+            let path = temp_dir.0.take().unwrap().keep();
+            panic!("assertion failed: unexpected filesystem state in `{}`\n{}", path.display(), fs_failures.join("\n"));
+        };
+
         let output = Output {
             status,
             stdout,
@@ -436,10 +816,23 @@ pub fn alter_path<T: AsRef<OsStr> + ?Sized>(path: &T, current_path: &'static str
 }
 
 #[doc(hidden)]
-pub fn wait(child: &mut Child, duration: Option<Duration>) -> ExitStatus {
+pub fn wait(child: &mut Child, duration: Option<Duration>, term_signal: Option<i32>, kill_grace: Option<Duration>, log: bool) -> ExitStatus {
     if let Some(duration) = duration {
         child.wait_timeout(duration).expect("Failed to wait for child process")
             .unwrap_or_else(|| {
+                #[cfg(unix)]
+                {
+                    if let Some(signal) = term_signal {
+                        send_signal(child, signal);
+                        let grace = kill_grace.unwrap_or_else(|| Duration::from_millis(1000));
+                        if let Some(status) = child.wait_timeout(grace).expect("Failed to wait for child process") {
+                            if log {
+                                println!("Child process terminated gracefully");
+                            };
+                            return status;
+                        };
+                    };
+                };
                 child.kill().expect("Failed to kill child process");
                 println!("Killed child process");
                 child.wait().unwrap()
@@ -449,6 +842,15 @@ pub fn wait(child: &mut Child, duration: Option<Duration>) -> ExitStatus {
     }
 }
 
+/// Send a raw signal to the child process without waiting for it.
+#[cfg(unix)]
+#[doc(hidden)]
+pub fn send_signal(child: &Child, signal: i32) {
+    unsafe {
+        nix::libc::kill(child.id() as nix::libc::pid_t, signal);
+    };
+}
+
 #[cfg(unix)]
 #[doc(hidden)]
 #[inline]
@@ -465,6 +867,114 @@ pub fn get_code(status: ExitStatus) -> i32 {
     status.code().unwrap()
 }
 
+/// Allocate a pseudo-terminal and attach the command's stdin, stdout and stderr
+/// to its slave end, returning the master end the child's output can be read from.
+#[cfg(unix)]
+#[doc(hidden)]
+pub fn attach_pty(command: &mut Command) -> std::fs::File {
+    use std::os::unix::io::AsRawFd;
+    use nix::pty::openpty;
+    use nix::unistd::{dup, setsid};
+
+    let pty = openpty(None, None).expect("Failed to allocate a pseudo-terminal");
+    let slave_fd = pty.slave.as_raw_fd();
+
+    command.stdout(Stdio::from(dup(slave_fd).expect("Failed to duplicate the pty slave")));
+    command.stderr(Stdio::from(dup(slave_fd).expect("Failed to duplicate the pty slave")));
+    command.stdin(Stdio::from(pty.slave));
+
+    unsafe {
+        command.pre_exec(move || {
+            setsid().map_err(|e| io::Error::from_raw_os_error(e as i32))?;
+            if nix::libc::ioctl(0, nix::libc::TIOCSCTTY as _, 0) < 0 {
+                return Err(io::Error::last_os_error());
+            };
+            Ok(())
+        });
+    };
+
+    std::fs::File::from(pty.master)
+}
+
+/// Convert the value given to the `rlimit` pseudo-object into a `(soft, hard)` pair.
+#[cfg(unix)]
+#[doc(hidden)]
+pub trait IntoRlimit {
+    fn into_rlimit(self) -> (u64, u64);
+}
+
+#[cfg(unix)]
+impl IntoRlimit for i32 {
+    fn into_rlimit(self) -> (u64, u64) {
+        let limit = rlim_from_i32(self);
+        (limit, limit)
+    }
+}
+
+#[cfg(unix)]
+impl IntoRlimit for (i32, i32) {
+    fn into_rlimit(self) -> (u64, u64) {
+        (rlim_from_i32(self.0), rlim_from_i32(self.1))
+    }
+}
+
+#[cfg(unix)]
+fn rlim_from_i32(value: i32) -> u64 {
+    if value < 0 {
+        nix::libc::RLIM_INFINITY
+    } else {
+        value as u64
+    }
+}
+
+/// Apply a single named `RLIMIT_*` resource limit to the child via `pre_exec`.
+#[cfg(unix)]
+#[doc(hidden)]
+pub fn set_rlimit(command: &mut Command, name: &'static str, soft: u64, hard: u64) {
+    use nix::sys::resource::{setrlimit, Resource};
+
+    let resource = match name {
+        "CPU" => Resource::RLIMIT_CPU,
+        "FSIZE" => Resource::RLIMIT_FSIZE,
+        "DATA" => Resource::RLIMIT_DATA,
+        "STACK" => Resource::RLIMIT_STACK,
+        "CORE" => Resource::RLIMIT_CORE,
+        "RSS" => Resource::RLIMIT_RSS,
+        "NOFILE" => Resource::RLIMIT_NOFILE,
+        "AS" => Resource::RLIMIT_AS,
+        "NPROC" => Resource::RLIMIT_NPROC,
+        "MEMLOCK" => Resource::RLIMIT_MEMLOCK,
+        other => panic!("Unknown rlimit resource `{}`", other)
+    };
+
+    unsafe {
+        command.pre_exec(move || {
+            setrlimit(resource, soft, hard).map_err(|e| io::Error::from_raw_os_error(e as i32))
+        });
+    };
+}
+
+/// Read the captured bytes of a pty master until the child hangs up,
+/// treating `EIO` the same as a regular EOF.
+#[cfg(unix)]
+#[doc(hidden)]
+pub fn read_pty(master: &mut std::fs::File) -> Vec<u8> {
+    use std::io::Read;
+
+    let mut buf = Vec::with_capacity(0xff);
+    let mut chunk = [0u8; 0x1000];
+    loop {
+        match master.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            Err(ref e) if e.raw_os_error() == Some(nix::libc::EIO) => break,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => panic!("Failed to read from the pty master: {}", e)
+        };
+    };
+    buf
+}
+
 #[doc(hidden)]
 pub fn assert<T, U>(a: &T, b: &U, message: &str) where
     T: Debug + PartialEq<U>,
@@ -474,6 +984,155 @@ pub fn assert<T, U>(a: &T, b: &U, message: &str) where
     };
 }
 
+#[doc(hidden)]
+pub fn assert_contains(actual: &[u8], expected: &[u8], message: &str) {
+    let found = expected.is_empty() ||
+        actual.windows(expected.len()).any(|window| window == expected);
+    if !found {
+        panic!("assertion failed: {}\nexpected to contain `{:?}`\nfound `{:?}`",
+            message, String::from_utf8_lossy(expected), String::from_utf8_lossy(actual));
+    };
+}
+
+#[doc(hidden)]
+pub fn assert_matches(actual: &[u8], pattern: &str, message: &str) {
+    let regex = Regex::new(pattern).expect("Invalid regex pattern");
+    let text = String::from_utf8_lossy(actual);
+    if !regex.is_match(&text) {
+        panic!("assertion failed: {}\nexpected to match `{}`\nfound `{:?}`", message, pattern, text);
+    };
+}
+
+#[doc(hidden)]
+pub fn assert_fn<F: Fn(&[u8]) -> bool>(actual: &[u8], predicate: F, message: &str) {
+    if !predicate(actual) {
+        panic!("assertion failed: {}\nfound `{:?}`", message, String::from_utf8_lossy(actual));
+    };
+}
+
+/// Owns the `fixtures` temp dir for the duration of `exec!`'s assertions. Deleting it is
+/// `TempDir`'s job on a normal drop, but if the thread is unwinding from a failed assertion
+/// we keep it around and print its path instead, so it isn't gone by the time the user
+/// reads the panic.
+#[doc(hidden)]
+pub struct TempDirGuard(pub Option<tempfile::TempDir>);
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            if let Some(dir) = self.0.take() {
+                eprintln!("preserved temp directory for debugging: {}", dir.keep().display());
+            };
+        };
+    }
+}
+
+#[doc(hidden)]
+pub fn write_fixture(dir: &std::path::Path, path: &str, content: &[u8]) {
+    let path = dir.join(path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).expect("Failed to create fixture parent directory");
+    };
+    std::fs::write(path, content).expect("Failed to write fixture file");
+}
+
+#[doc(hidden)]
+pub fn check_expect_file(dir: &std::path::Path, path: &str, expected: &[u8]) -> Option<String> {
+    match std::fs::read(dir.join(path)) {
+        Ok(ref actual) if actual.as_slice() == expected => None,
+        Ok(ref actual) => Some(format!("expected `{}` to contain `{:?}`\nfound `{:?}`",
+            path, String::from_utf8_lossy(expected), String::from_utf8_lossy(actual))),
+        Err(error) => Some(format!("expected `{}` to exist, but could not be read: {}", path, error))
+    }
+}
+
+#[doc(hidden)]
+pub fn check_expect_absent(dir: &std::path::Path, path: &str) -> Option<String> {
+    if dir.join(path).exists() {
+        Some(format!("expected `{}` to be absent, but it exists", path))
+    } else {
+        None
+    }
+}
+
+/// A single step of a `script`, built through [`expect()`] or [`send()`].
+///
+/// [`expect()`]: fn.expect.html
+/// [`send()`]: fn.send.html
+pub enum ScriptStep {
+    Expect(Vec<u8>),
+    Send(Vec<u8>)
+}
+
+/// Wait until `bytes` appears in the accumulated stdout of a `script`.
+pub fn expect<T: AsRef<[u8]>>(bytes: T) -> ScriptStep {
+    ScriptStep::Expect(bytes.as_ref().to_vec())
+}
+
+/// Write `bytes` to the stdin of a `script`-driven program.
+pub fn send<T: AsRef<[u8]>>(bytes: T) -> ScriptStep {
+    ScriptStep::Send(bytes.as_ref().to_vec())
+}
+
+/// Drive a `script` against a freshly spawned child: a background thread reads
+/// stdout incrementally into a shared buffer while this thread walks through
+/// the steps, waiting for each `expect()` (within `timeout`, if any) before
+/// writing the next `send()`. Returns everything read from stdout.
+#[doc(hidden)]
+pub fn run_script(child: &mut Child, steps: Vec<ScriptStep>, timeout: Option<Duration>) -> Vec<u8> {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let reader_buffer = buffer.clone();
+    let mut child_stdout = mem::replace(&mut child.stdout, None).expect("`script` requires stdout to be piped");
+
+    let reader = thread::spawn(move || {
+        let mut chunk = [0u8; 0x1000];
+        loop {
+            match child_stdout.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => reader_buffer.lock().unwrap().extend_from_slice(&chunk[..n])
+            };
+        };
+    });
+
+    let mut stdin = mem::replace(&mut child.stdin, None);
+    let step_timeout = timeout.unwrap_or_else(|| Duration::from_secs(i32::MAX as u64));
+
+    for step in steps {
+        match step {
+            ScriptStep::Expect(expected) => {
+                let start = Instant::now();
+                loop {
+                    let seen = buffer.lock().unwrap().clone();
+                    if expected.is_empty() || seen.windows(expected.len()).any(|window| window == expected.as_slice()) {
+                        break;
+                    };
+                    if start.elapsed() >= step_timeout {
+                        // drop stdin first so a child blocked on a read of it can unwind on its own;
+                        // then kill it outright so one that ignores/never reads stdin isn't orphaned
+                        mem::drop(stdin.take());
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        panic!("assertion failed: script step did not appear in time\nexpected `{:?}`\nfound so far `{:?}`",
+                            String::from_utf8_lossy(&expected), String::from_utf8_lossy(&seen));
+                    };
+                    thread::sleep(Duration::from_millis(10));
+                };
+            },
+            ScriptStep::Send(bytes) => {
+                stdin.as_mut()
+                    .expect("`script` requires stdin to be piped")
+                    .write_all(&bytes)
+                    .expect("Failed to write to stdin");
+            }
+        };
+    };
+
+    // close stdin so a child that keeps reading it sees EOF and exits, letting stdout hit EOF
+    mem::drop(stdin.take());
+    reader.join().expect("The script reader thread panicked");
+    Arc::try_unwrap(buffer).unwrap().into_inner().unwrap()
+}
+
 /// Input type checking, only has to compile
 #[cold]
 fn possible_input() {
@@ -517,4 +1176,38 @@ fn possible_input() {
         stdout: a,
         stderr: a
     };
+
+    // predicate matching
+    exec! {
+        "",
+        stdout_contains: b"b",
+        stdout_matches: "^b$",
+        stdout_fn: |bytes: &[u8]| !bytes.is_empty(),
+        stderr_contains: [],
+        stderr_matches: "",
+        stderr_fn: |_: &[u8]| true
+    };
+
+    // temp working directory fixtures
+    exec! {
+        "",
+        fixtures: {
+            "in.txt" => b"data",
+            "nested/in.txt" => vec![1, 2, 3]
+        },
+        expect_file: {
+            "in.txt" => b"data"
+        },
+        expect_absent: ["out.txt", "nested/out.txt"]
+    };
+
+    // interactive send/expect scripting
+    exec! {
+        "",
+        script: vec![
+            expect(b"Name: "),
+            send(b"root\n"),
+            expect(b"Welcome, root!\n")
+        ]
+    };
 }
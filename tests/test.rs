@@ -2,6 +2,7 @@
 
 #[macro_use]
 extern crate test_exec;
+extern crate tempfile;
 
 #[test]
 fn test_args() {
@@ -160,6 +161,171 @@ fn test_signal_ignored() {
     };
 }
 
+#[test]
+fn test_predicate_matching() {
+    exec! {
+        "echo",
+        args: ["Hello world!"],
+        log: true,
+
+        stdout_contains: b"world",
+        stdout_matches: r"^Hello \w+!\n$",
+        stdout_fn: |bytes: &[u8]| !bytes.is_empty(),
+        stderr_contains: [],
+        stderr_matches: "^$",
+        stderr_fn: |bytes: &[u8]| bytes.is_empty()
+    };
+}
+
+#[cfg(unix)]
+#[test]
+#[should_panic]
+fn test_predicate_matching_failure() {
+    exec! {
+        "echo",
+        args: ["Hello world!"],
+        log: true,
+
+        stdout_contains: b"goodbye"
+    };
+}
+
+#[test]
+fn test_script() {
+    exec! {
+        "login_prompt",
+        script: vec![
+            expect(b"Name: "),
+            send(b"root\n"),
+            expect(b"Welcome, root!\n")
+        ],
+        log: true
+    };
+}
+
+#[test]
+#[should_panic]
+fn test_script_timeout() {
+    exec! {
+        "login_prompt",
+        script: vec![
+            expect(b"never printed"),
+        ],
+        timeout: 500
+    };
+}
+
+#[test]
+fn test_fixtures() {
+    exec! {
+        "cp",
+        args: ["in.txt", "out.txt"],
+        log: true,
+        fixtures: {
+            "in.txt" => b"data"
+        },
+
+        expect_file: {
+            "in.txt" => b"data",
+            "out.txt" => b"data"
+        }
+    };
+}
+
+#[test]
+#[should_panic]
+fn test_fixtures_cwd_conflict() {
+    exec! {
+        "true",
+        cwd: "/",
+        fixtures: {
+            "in.txt" => b"data"
+        }
+    };
+}
+
+#[test]
+fn test_expect_absent() {
+    exec! {
+        "rm",
+        args: ["lock"],
+        log: true,
+        fixtures: {
+            "lock" => b""
+        },
+
+        expect_absent: ["lock"]
+    };
+}
+
+#[test]
+#[should_panic]
+fn test_expect_file_failure() {
+    exec! {
+        "true",
+        fixtures: {
+            "in.txt" => b"data"
+        },
+
+        expect_file: {
+            "in.txt" => b"wrong"
+        }
+    };
+}
+
+#[cfg(unix)]
+#[test]
+fn test_graceful_timeout() {
+    exec! {
+        "sleep",
+        args: ["60"],
+        timeout: 3000,
+        kill_grace: 500,
+        log: true,
+
+        code: 15, // SIGTERM
+        signal: 15
+    };
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rlimit() {
+    let dir = tempfile::tempdir().expect("Failed to create a temporary directory");
+    let out = dir.path().join("test_exec_rlimit_out");
+    let args: Vec<String> = vec![
+        "if=/dev/zero".to_string(),
+        format!("of={}", out.display()),
+        "bs=1024".to_string(),
+        "count=2".to_string()
+    ];
+
+    exec! {
+        "dd",
+        args: args,
+        log: true,
+        rlimit: {
+            FSIZE: 512
+        },
+
+        code: 25, // SIGXFSZ, the default uncaught disposition for exceeding RLIMIT_FSIZE
+        signal: 25
+    };
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pty() {
+    exec! {
+        "print_stdin",
+        stdin: b"meow",
+        log: true,
+        pty: true,
+
+        stdout_contains: b"meow"
+    };
+}
+
 #[test]
 fn test_stdin() {
     exec! {